@@ -7,14 +7,18 @@
 
 use crate::{BLOCK_SIZE, CONSTANTS, IV_SIZE, KEY_SIZE, STATE_WORDS};
 use core::{convert::TryInto, mem};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 /// Size of buffers passed to `generate` and `apply_keystream` for this backend
 pub(crate) const BUFFER_SIZE: usize = BLOCK_SIZE;
 
-/// The ChaCha20 block function (portable software implementation)
-// TODO(tarcieri): zeroize?
+/// The ChaCha20 block function (portable software implementation), using
+/// the original Bernstein layout: a 64-bit block counter (words 12-13) and
+/// a 64-bit nonce (words 14-15). See [`IetfBlock`] for the RFC 8439 layout.
 #[allow(dead_code)]
 #[derive(Clone)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
 pub(crate) struct Block {
     /// Internal state of the block function
     state: [u32; STATE_WORDS],
@@ -58,6 +62,9 @@ impl Block {
         for (i, chunk) in output.chunks_mut(4).enumerate() {
             chunk.copy_from_slice(&state[i].to_le_bytes());
         }
+
+        #[cfg(feature = "zeroize")]
+        state.zeroize();
     }
 
     /// Apply generated keystream to the output buffer
@@ -73,6 +80,9 @@ impl Block {
                 *a ^= *b;
             }
         }
+
+        #[cfg(feature = "zeroize")]
+        state.zeroize();
     }
 
     #[inline]
@@ -103,6 +113,279 @@ impl Block {
     }
 }
 
+/// The ChaCha20 block function using the RFC 8439 (IETF) layout: a 32-bit
+/// block counter in word 12 and a 96-bit nonce in words 13-15, rather than
+/// [`Block`]'s original Bernstein layout of a 64-bit counter (words 12-13)
+/// and a 64-bit nonce (words 14-15).
+///
+/// Splitting these into distinct types prevents a caller from silently
+/// pairing a 64-bit counter with a 96-bit nonce or vice versa.
+#[allow(dead_code)]
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub(crate) struct IetfBlock {
+    /// Internal state of the block function
+    state: [u32; STATE_WORDS],
+
+    /// Number of rounds to perform
+    rounds: usize,
+}
+
+#[allow(dead_code)]
+impl IetfBlock {
+    /// Initialize block function with the given key, 96-bit nonce, and number of rounds
+    pub(crate) fn new(key: &[u8; KEY_SIZE], nonce: [u8; 12], rounds: usize) -> Self {
+        assert!(
+            rounds == 8 || rounds == 12 || rounds == 20,
+            "rounds must be 8, 12, or 20"
+        );
+
+        let mut state: [u32; STATE_WORDS] = unsafe { mem::zeroed() };
+        state[..4].copy_from_slice(&CONSTANTS);
+
+        for (i, chunk) in key.chunks(4).enumerate() {
+            state[4 + i] = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        state[12] = 0;
+
+        for (i, chunk) in nonce.chunks(4).enumerate() {
+            state[13 + i] = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        Self { state, rounds }
+    }
+
+    /// Generate output, overwriting data already in the buffer
+    pub(crate) fn generate(&mut self, counter: u32, output: &mut [u8]) {
+        debug_assert_eq!(output.len(), BUFFER_SIZE);
+        self.counter_setup(counter);
+
+        let mut state = self.state;
+        self.rounds(&mut state);
+
+        for (i, chunk) in output.chunks_mut(4).enumerate() {
+            chunk.copy_from_slice(&state[i].to_le_bytes());
+        }
+
+        #[cfg(feature = "zeroize")]
+        state.zeroize();
+    }
+
+    /// Apply generated keystream to the output buffer
+    pub(crate) fn apply_keystream(&mut self, counter: u32, output: &mut [u8]) {
+        debug_assert_eq!(output.len(), BUFFER_SIZE);
+        self.counter_setup(counter);
+
+        let mut state = self.state;
+        self.rounds(&mut state);
+
+        for (i, chunk) in output.chunks_mut(4).enumerate() {
+            for (a, b) in chunk.iter_mut().zip(&state[i].to_le_bytes()) {
+                *a ^= *b;
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        state.zeroize();
+    }
+
+    #[inline]
+    fn counter_setup(&mut self, counter: u32) {
+        // RFC 8439 only allocates a single 32-bit counter word, giving a
+        // maximum keystream length of 2^32 blocks (256 GiB) per nonce.
+        self.state[12] = counter;
+    }
+
+    #[inline]
+    fn rounds(&mut self, state: &mut [u32; STATE_WORDS]) {
+        for _ in 0..(self.rounds / 2) {
+            // column rounds
+            quarter_round(0, 4, 8, 12, state);
+            quarter_round(1, 5, 9, 13, state);
+            quarter_round(2, 6, 10, 14, state);
+            quarter_round(3, 7, 11, 15, state);
+
+            // diagonal rounds
+            quarter_round(0, 5, 10, 15, state);
+            quarter_round(1, 6, 11, 12, state);
+            quarter_round(2, 7, 8, 13, state);
+            quarter_round(3, 4, 9, 14, state);
+        }
+
+        for (s1, s0) in state.iter_mut().zip(&self.state) {
+            *s1 = s1.wrapping_add(*s0);
+        }
+    }
+}
+
+/// The HChaCha20 function: derives a 256-bit subkey from a key and a 16-byte
+/// nonce prefix. Used to extend ChaCha20 to the 192-bit (24-byte) nonces of
+/// XChaCha20. Defined in the XChaCha20 draft spec, Section 2.2:
+///
+/// <https://tools.ietf.org/html/draft-irtf-cfrg-xchacha-03#section-2.2>
+///
+/// Builds the same 16-word state as [`Block::new`], but with the nonce
+/// occupying words 12-15 rather than the IV and counter, always runs the
+/// full 20 rounds (10 double-rounds), and skips the final feed-forward
+/// addition of the original state, returning words 0-3 and 12-15 directly.
+#[allow(dead_code)]
+pub(crate) fn hchacha20(key: &[u8; KEY_SIZE], nonce16: &[u8; 16]) -> [u8; 32] {
+    let mut state: [u32; STATE_WORDS] = unsafe { mem::zeroed() };
+    state[..4].copy_from_slice(&CONSTANTS);
+
+    for (i, chunk) in key.chunks(4).enumerate() {
+        state[4 + i] = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+
+    for (i, chunk) in nonce16.chunks(4).enumerate() {
+        state[12 + i] = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+
+    for _ in 0..10 {
+        // column rounds
+        quarter_round(0, 4, 8, 12, &mut state);
+        quarter_round(1, 5, 9, 13, &mut state);
+        quarter_round(2, 6, 10, 14, &mut state);
+        quarter_round(3, 7, 11, 15, &mut state);
+
+        // diagonal rounds
+        quarter_round(0, 5, 10, 15, &mut state);
+        quarter_round(1, 6, 11, 12, &mut state);
+        quarter_round(2, 7, 8, 13, &mut state);
+        quarter_round(3, 4, 9, 14, &mut state);
+    }
+
+    let mut subkey = [0u8; 32];
+    subkey[..4].copy_from_slice(&state[0].to_le_bytes());
+    subkey[4..8].copy_from_slice(&state[1].to_le_bytes());
+    subkey[8..12].copy_from_slice(&state[2].to_le_bytes());
+    subkey[12..16].copy_from_slice(&state[3].to_le_bytes());
+    subkey[16..20].copy_from_slice(&state[12].to_le_bytes());
+    subkey[20..24].copy_from_slice(&state[13].to_le_bytes());
+    subkey[24..28].copy_from_slice(&state[14].to_le_bytes());
+    subkey[28..].copy_from_slice(&state[15].to_le_bytes());
+    subkey
+}
+
+/// The XChaCha20 block function (portable software implementation).
+///
+/// Extends [`Block`] to the 192-bit (24-byte) nonces of XChaCha20 by
+/// deriving a one-time subkey with [`hchacha20`] from the key and the first
+/// 16 bytes of the nonce, then running the ordinary ChaCha20 block function
+/// keyed with that subkey, using the remaining 8 nonce bytes as its IV.
+#[allow(dead_code)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub(crate) struct XBlock {
+    /// Inner ChaCha20 block function, keyed with the HChaCha20 subkey
+    block: Block,
+}
+
+#[allow(dead_code)]
+impl XBlock {
+    /// Initialize block function with the given key, 24-byte extended nonce,
+    /// and number of rounds
+    pub(crate) fn new(key: &[u8; KEY_SIZE], nonce24: [u8; 24], rounds: usize) -> Self {
+        let subkey = hchacha20(key, nonce24[..16].try_into().unwrap());
+
+        // The IETF XChaCha20 construction conceptually runs the inner
+        // ChaCha20 with a 12-byte nonce of 4 zero bytes followed by these
+        // last 8 nonce bytes, and a 32-bit counter in word 12. That is
+        // keystream-equivalent to `Block`'s 64-bit counter (words 12-13)
+        // plus 64-bit IV (words 14-15) layout used here, as long as the
+        // counter's high word (word 13) stays zero, i.e. for the first
+        // 2^32 blocks. `generate`/`apply_keystream` accept any `u64`
+        // counter without checking it; for counters above `u32::MAX` the
+        // output no longer matches IETF XChaCha20.
+        let mut iv = [0u8; IV_SIZE];
+        iv.copy_from_slice(&nonce24[16..]);
+
+        Self {
+            block: Block::new(&subkey, iv, rounds),
+        }
+    }
+
+    /// Generate output, overwriting data already in the buffer
+    pub(crate) fn generate(&mut self, counter: u64, output: &mut [u8]) {
+        self.block.generate(counter, output);
+    }
+
+    /// Apply generated keystream to the output buffer
+    pub(crate) fn apply_keystream(&mut self, counter: u64, output: &mut [u8]) {
+        self.block.apply_keystream(counter, output);
+    }
+}
+
+/// A cursor over a [`Block`]'s keystream, supporting random-access seeking
+/// to an arbitrary byte offset and `apply_keystream` calls of any length,
+/// independent of `BLOCK_SIZE` boundaries.
+///
+/// Internally this tracks a 64-bit block counter and an intra-block byte
+/// offset, buffering one block's worth of keystream at a time so that
+/// `apply_keystream` can resume mid-block.
+#[allow(dead_code)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub(crate) struct Seeker {
+    /// Block function producing the keystream
+    block: Block,
+
+    /// Counter of the block the buffered keystream was generated from
+    counter: u64,
+
+    /// Keystream generated for `counter`
+    keystream: [u8; BLOCK_SIZE],
+
+    /// Byte offset into `keystream` of the next unconsumed keystream byte
+    offset: usize,
+}
+
+#[allow(dead_code)]
+impl Seeker {
+    /// Wrap a [`Block`], starting at the beginning of its keystream
+    pub(crate) fn new(block: Block) -> Self {
+        let mut seeker = Self {
+            block,
+            counter: 0,
+            keystream: [0u8; BLOCK_SIZE],
+            offset: 0,
+        };
+        seeker.block.generate(0, &mut seeker.keystream);
+        seeker
+    }
+
+    /// Seek to the given byte offset in the keystream
+    pub(crate) fn seek(&mut self, pos: u64) {
+        self.counter = pos / BLOCK_SIZE as u64;
+        self.offset = (pos % BLOCK_SIZE as u64) as usize;
+        self.block.generate(self.counter, &mut self.keystream);
+    }
+
+    /// The current byte position in the keystream
+    pub(crate) fn position(&self) -> u64 {
+        self.counter * BLOCK_SIZE as u64 + self.offset as u64
+    }
+
+    /// Apply keystream to `data`, resuming from the current position and
+    /// advancing it by `data.len()` bytes
+    pub(crate) fn apply_keystream(&mut self, mut data: &mut [u8]) {
+        while !data.is_empty() {
+            if self.offset == BLOCK_SIZE {
+                self.counter = self.counter.checked_add(1).expect("counter overflow");
+                self.block.generate(self.counter, &mut self.keystream);
+                self.offset = 0;
+            }
+
+            let n = data.len().min(BLOCK_SIZE - self.offset);
+            for (a, b) in data[..n].iter_mut().zip(&self.keystream[self.offset..]) {
+                *a ^= *b;
+            }
+
+            self.offset += n;
+            data = &mut data[n..];
+        }
+    }
+}
+
 /// The ChaCha20 quarter round function
 #[inline]
 pub(crate) fn quarter_round(
@@ -127,4 +410,101 @@ pub(crate) fn quarter_round(
     state[c] = state[c].wrapping_add(state[d]);
     state[b] ^= state[c];
     state[b] = state[b].rotate_left(7);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// HChaCha20 subkey derivation test vector from the XChaCha20 draft spec:
+    /// <https://tools.ietf.org/html/draft-irtf-cfrg-xchacha-03#section-2.2.1>
+    #[test]
+    fn hchacha20_draft_vector() {
+        let key = {
+            let mut key = [0u8; KEY_SIZE];
+            for (i, byte) in key.iter_mut().enumerate() {
+                *byte = i as u8;
+            }
+            key
+        };
+
+        let nonce16 = [
+            0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00, 0x31, 0x41,
+            0x59, 0x27,
+        ];
+
+        let expected = [
+            0x82, 0x41, 0x3b, 0x42, 0x27, 0xb2, 0x7b, 0xfe, 0xd3, 0x0e, 0x42, 0x50, 0x8a, 0x87,
+            0x7d, 0x73, 0xa0, 0xf9, 0xe4, 0xd5, 0x8a, 0x74, 0xa8, 0x53, 0xc1, 0x2e, 0xc4, 0x13,
+            0x26, 0xd3, 0xec, 0xdc,
+        ];
+
+        assert_eq!(hchacha20(&key, &nonce16), expected);
+    }
+
+    /// XChaCha20 first keystream block for the draft spec's example key and
+    /// 24-byte nonce (the same key as above, nonce
+    /// `404142434445464748494a4b4c4d4e4f5051525354555657`), computed against
+    /// an independent reference implementation.
+    #[test]
+    fn xchacha20_keystream_vector() {
+        let key = {
+            let mut key = [0u8; KEY_SIZE];
+            for (i, byte) in key.iter_mut().enumerate() {
+                *byte = i as u8;
+            }
+            key
+        };
+
+        let nonce24 = [
+            0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4a, 0x4b, 0x4c, 0x4d,
+            0x4e, 0x4f, 0x50, 0x51, 0x52, 0x53, 0x54, 0x55, 0x56, 0x57,
+        ];
+
+        let expected = [
+            0x85, 0xee, 0x31, 0x16, 0x33, 0x7d, 0x23, 0xc6, 0x22, 0x15, 0x34, 0x5c, 0x52, 0x26,
+            0x4d, 0x7f, 0x3c, 0x6e, 0x8a, 0x93, 0x59, 0x30, 0x4f, 0xdc, 0x84, 0x53, 0x18, 0x04,
+            0x83, 0xac, 0x16, 0x66, 0x3f, 0xb7, 0x04, 0x8e, 0x48, 0x61, 0x98, 0xe5, 0x4e, 0xb8,
+            0x11, 0x95, 0x3b, 0xf0, 0xdc, 0x76, 0xa7, 0x67, 0xa9, 0xd2, 0x91, 0x34, 0xda, 0xe8,
+            0xad, 0x69, 0x25, 0x19, 0xaf, 0xd7, 0xb6, 0xd8,
+        ];
+
+        let mut block = XBlock::new(&key, nonce24, 20);
+        let mut output = [0u8; BUFFER_SIZE];
+        block.generate(0, &mut output);
+
+        assert_eq!(output, expected);
+    }
+
+    /// RFC 8439 §2.3.2 test vector for the IETF counter(word 12)/nonce
+    /// (words 13-15) layout:
+    /// <https://tools.ietf.org/html/rfc8439#section-2.3.2>
+    #[test]
+    fn ietf_block_rfc8439_vector() {
+        let key = {
+            let mut key = [0u8; KEY_SIZE];
+            for (i, byte) in key.iter_mut().enumerate() {
+                *byte = i as u8;
+            }
+            key
+        };
+
+        let nonce = [
+            0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let expected = [
+            0x10, 0xf1, 0xe7, 0xe4, 0xd1, 0x3b, 0x59, 0x15, 0x50, 0x0f, 0xdd, 0x1f, 0xa3, 0x20,
+            0x71, 0xc4, 0xc7, 0xd1, 0xf4, 0xc7, 0x33, 0xc0, 0x68, 0x03, 0x04, 0x22, 0xaa, 0x9a,
+            0xc3, 0xd4, 0x6c, 0x4e, 0xd2, 0x82, 0x64, 0x46, 0x07, 0x9f, 0xaa, 0x09, 0x14, 0xc2,
+            0xd7, 0x05, 0xd9, 0x8b, 0x02, 0xa2, 0xb5, 0x12, 0x9c, 0xd1, 0xde, 0x16, 0x4e, 0xb9,
+            0xcb, 0xd0, 0x83, 0xe8, 0xa2, 0x50, 0x3c, 0x4e,
+        ];
+
+        let mut block = IetfBlock::new(&key, nonce, 20);
+        let mut output = [0u8; BUFFER_SIZE];
+        block.generate(1, &mut output);
+
+        assert_eq!(output, expected);
+    }
+}
@@ -0,0 +1,204 @@
+//! Wide (4-block) ChaCha20 block function. Defined in RFC 8439 Section 2.3.
+//!
+//! <https://tools.ietf.org/html/rfc8439#section-2.3>
+//!
+//! Portable implementation which processes four counter-incremented blocks
+//! per call using a transposed state layout, so that LLVM's auto-vectorizer
+//! can lower the lane-wise arithmetic to SSE2/AVX2 without relying on
+//! architecture-specific intrinsics.
+
+use crate::{BLOCK_SIZE, CONSTANTS, IV_SIZE, KEY_SIZE, STATE_WORDS};
+use core::convert::TryInto;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+/// Number of blocks processed per call to [`Block4::generate4`] /
+/// [`Block4::apply_keystream4`]
+const LANES: usize = 4;
+
+/// Size of buffers passed to `generate4` and `apply_keystream4`
+pub(crate) const BUFFER_SIZE: usize = LANES * BLOCK_SIZE;
+
+/// The ChaCha20 block function, processing four blocks at a time.
+///
+/// Rather than the flat `[u32; STATE_WORDS]` state used by the single-block
+/// backend, state is kept as `STATE_WORDS` lanes of `[u32; LANES]`, one
+/// element per in-flight block. Each `quarter_round` becomes a lane-wise
+/// wrapping add / xor / rotate, which the compiler can auto-vectorize.
+#[allow(dead_code)]
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub(crate) struct Block4 {
+    /// Internal state of the block function, transposed into per-word lanes
+    state: [[u32; LANES]; STATE_WORDS],
+
+    /// Number of rounds to perform
+    rounds: usize,
+}
+
+#[allow(dead_code)]
+impl Block4 {
+    /// Initialize block function with the given key size, IV, and number of rounds
+    pub(crate) fn new(key: &[u8; KEY_SIZE], iv: [u8; IV_SIZE], rounds: usize) -> Self {
+        assert!(
+            rounds == 8 || rounds == 12 || rounds == 20,
+            "rounds must be 8, 12, or 20"
+        );
+
+        let mut state = [[0u32; LANES]; STATE_WORDS];
+
+        for (i, &word) in CONSTANTS.iter().enumerate() {
+            state[i] = [word; LANES];
+        }
+
+        for (i, chunk) in key.chunks(4).enumerate() {
+            state[4 + i] = [u32::from_le_bytes(chunk.try_into().unwrap()); LANES];
+        }
+
+        state[12] = [0; LANES];
+        state[13] = [0; LANES];
+        state[14] = [u32::from_le_bytes(iv[0..4].try_into().unwrap()); LANES];
+        state[15] = [u32::from_le_bytes(iv[4..].try_into().unwrap()); LANES];
+
+        Self { state, rounds }
+    }
+
+    /// Generate output, overwriting data already in the buffer
+    pub(crate) fn generate4(&mut self, counter: u64, output: &mut [u8]) {
+        debug_assert_eq!(output.len(), BUFFER_SIZE);
+        self.counter_setup(counter);
+
+        let mut state = self.state;
+        self.rounds(&mut state);
+
+        for lane in 0..LANES {
+            let block = &mut output[lane * BLOCK_SIZE..(lane + 1) * BLOCK_SIZE];
+            for (i, chunk) in block.chunks_mut(4).enumerate() {
+                chunk.copy_from_slice(&state[i][lane].to_le_bytes());
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        state.zeroize();
+    }
+
+    /// Apply generated keystream to the output buffer
+    pub(crate) fn apply_keystream4(&mut self, counter: u64, output: &mut [u8]) {
+        debug_assert_eq!(output.len(), BUFFER_SIZE);
+        self.counter_setup(counter);
+
+        let mut state = self.state;
+        self.rounds(&mut state);
+
+        for lane in 0..LANES {
+            let block = &mut output[lane * BLOCK_SIZE..(lane + 1) * BLOCK_SIZE];
+            for (i, chunk) in block.chunks_mut(4).enumerate() {
+                for (a, b) in chunk.iter_mut().zip(&state[i][lane].to_le_bytes()) {
+                    *a ^= *b;
+                }
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        state.zeroize();
+    }
+
+    #[inline]
+    fn counter_setup(&mut self, counter: u64) {
+        for lane in 0..LANES {
+            let block_counter = counter + lane as u64;
+            self.state[12][lane] = (block_counter & 0xffff_ffff) as u32;
+            self.state[13][lane] = ((block_counter >> 32) & 0xffff_ffff) as u32;
+        }
+    }
+
+    #[inline]
+    fn rounds(&mut self, state: &mut [[u32; LANES]; STATE_WORDS]) {
+        for _ in 0..(self.rounds / 2) {
+            // column rounds
+            quarter_round_x4(0, 4, 8, 12, state);
+            quarter_round_x4(1, 5, 9, 13, state);
+            quarter_round_x4(2, 6, 10, 14, state);
+            quarter_round_x4(3, 7, 11, 15, state);
+
+            // diagonal rounds
+            quarter_round_x4(0, 5, 10, 15, state);
+            quarter_round_x4(1, 6, 11, 12, state);
+            quarter_round_x4(2, 7, 8, 13, state);
+            quarter_round_x4(3, 4, 9, 14, state);
+        }
+
+        for (s1, s0) in state.iter_mut().zip(&self.state) {
+            for lane in 0..LANES {
+                s1[lane] = s1[lane].wrapping_add(s0[lane]);
+            }
+        }
+    }
+}
+
+/// The ChaCha20 quarter round function, applied lane-wise across four blocks
+#[inline]
+fn quarter_round_x4(
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+    state: &mut [[u32; LANES]; STATE_WORDS],
+) {
+    for lane in 0..LANES {
+        state[a][lane] = state[a][lane].wrapping_add(state[b][lane]);
+        state[d][lane] ^= state[a][lane];
+        state[d][lane] = state[d][lane].rotate_left(16);
+
+        state[c][lane] = state[c][lane].wrapping_add(state[d][lane]);
+        state[b][lane] ^= state[c][lane];
+        state[b][lane] = state[b][lane].rotate_left(12);
+
+        state[a][lane] = state[a][lane].wrapping_add(state[b][lane]);
+        state[d][lane] ^= state[a][lane];
+        state[d][lane] = state[d][lane].rotate_left(8);
+
+        state[c][lane] = state[c][lane].wrapping_add(state[d][lane]);
+        state[b][lane] ^= state[c][lane];
+        state[b][lane] = state[b][lane].rotate_left(7);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::soft::Block;
+
+    /// `generate4`'s four transposed lanes must produce exactly the same
+    /// keystream as four sequential single-block `generate` calls at
+    /// consecutive counters, pinning the lane/word layout against
+    /// regressions.
+    #[test]
+    fn generate4_matches_sequential_single_block() {
+        let key = {
+            let mut key = [0u8; KEY_SIZE];
+            for (i, byte) in key.iter_mut().enumerate() {
+                *byte = i as u8;
+            }
+            key
+        };
+
+        let iv = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88];
+        let counter = 41;
+
+        let mut wide = Block4::new(&key, iv, 20);
+        let mut wide_output = [0u8; BUFFER_SIZE];
+        wide.generate4(counter, &mut wide_output);
+
+        let mut single = Block::new(&key, iv, 20);
+        let mut single_output = [0u8; BUFFER_SIZE];
+        for lane in 0..LANES {
+            single.generate(
+                counter + lane as u64,
+                &mut single_output[lane * BLOCK_SIZE..(lane + 1) * BLOCK_SIZE],
+            );
+        }
+
+        assert_eq!(wide_output, single_output);
+    }
+}
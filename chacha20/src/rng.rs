@@ -0,0 +1,175 @@
+//! A ChaCha20-based CSPRNG, implemented on top of the same [`Block`]
+//! function used for encryption.
+//!
+//! Gated behind the `rng` feature. Implements [`rand_core::SeedableRng`]
+//! (keying from a 32-byte seed with an all-zero nonce) and
+//! [`rand_core::RngCore`] / [`rand_core::CryptoRng`], buffering one
+//! keystream block at a time and refilling on demand. Always uses 20
+//! rounds; no 8- or 12-round variant is provided.
+
+use crate::{block::soft::Block, IV_SIZE};
+use core::convert::TryInto;
+use rand_core::{impls, CryptoRng, Error, RngCore, SeedableRng};
+
+/// Number of 32-bit words in a keystream block
+const WORDS_PER_BLOCK: u64 = crate::BLOCK_SIZE as u64 / 4;
+
+/// A ChaCha20-based random number generator.
+///
+/// Generates its keystream from a zero nonce using [`Block`], the same
+/// portable block function the cipher uses, buffering one block at a time.
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct ChaChaRng {
+    /// Block function producing the keystream
+    block: Block,
+
+    /// Buffered keystream for `buffer_counter`
+    buffer: [u8; crate::BLOCK_SIZE],
+
+    /// Counter of the block currently held in `buffer`
+    buffer_counter: u64,
+
+    /// Byte offset into `buffer` of the next unconsumed keystream byte
+    index: usize,
+}
+
+impl ChaChaRng {
+    /// Number of rounds used by this RNG (fixed at the standard 20; this
+    /// module has no reduced-round variant)
+    const ROUNDS: usize = 20;
+
+    /// Refill `buffer` with the keystream for the next block
+    fn refill(&mut self) {
+        self.buffer_counter += 1;
+        self.block.generate(self.buffer_counter, &mut self.buffer);
+        self.index = 0;
+    }
+
+    /// Get the current position in the keystream, in 32-bit words
+    pub fn get_word_pos(&self) -> u64 {
+        self.buffer_counter * WORDS_PER_BLOCK + (self.index as u64) / 4
+    }
+
+    /// Set the current position in the keystream, in 32-bit words
+    pub fn set_word_pos(&mut self, word_pos: u64) {
+        self.buffer_counter = word_pos / WORDS_PER_BLOCK;
+        self.block.generate(self.buffer_counter, &mut self.buffer);
+        self.index = ((word_pos % WORDS_PER_BLOCK) * 4) as usize;
+    }
+}
+
+impl SeedableRng for ChaChaRng {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut block = Block::new(&seed, [0u8; IV_SIZE], Self::ROUNDS);
+        let mut buffer = [0u8; crate::BLOCK_SIZE];
+        block.generate(0, &mut buffer);
+
+        Self {
+            block,
+            buffer,
+            buffer_counter: 0,
+            index: 0,
+        }
+    }
+}
+
+impl RngCore for ChaChaRng {
+    fn next_u32(&mut self) -> u32 {
+        if self.index + 4 > crate::BLOCK_SIZE {
+            self.refill();
+        }
+
+        let word = u32::from_le_bytes(self.buffer[self.index..self.index + 4].try_into().unwrap());
+        self.index += 4;
+        word
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // Built on top of `next_u32` (rather than copying out of `buffer`
+        // directly) so `index` only ever advances in whole words, keeping
+        // it consistent with `get_word_pos`/`set_word_pos`. A `dest` whose
+        // length isn't a multiple of 4 still consumes a full word for the
+        // final partial chunk, matching `next_u32`'s granularity. This is
+        // a deliberate divergence from `rand_chacha`, whose `fill_bytes` is
+        // byte-exact against its `next_u32`/`next_u64` stream; byte-exact
+        // drop-in reproducibility with `rand_chacha` output is explicitly
+        // not a goal here, only internal consistency between this type's
+        // own `fill_bytes`, `next_u32`/`next_u64`, and word-position API.
+        let mut chunks = dest.chunks_exact_mut(4);
+
+        for chunk in &mut chunks {
+            chunk.copy_from_slice(&self.next_u32().to_le_bytes());
+        }
+
+        let remainder = chunks.into_remainder();
+        if !remainder.is_empty() {
+            let word = self.next_u32().to_le_bytes();
+            remainder.copy_from_slice(&word[..remainder.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for ChaChaRng {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed() -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        for (i, byte) in seed.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        seed
+    }
+
+    /// `ChaChaRng`'s keystream must match the zero-nonce [`Block`] keystream
+    /// it's built on, block for block.
+    #[test]
+    fn keystream_matches_zero_nonce_block() {
+        let mut rng = ChaChaRng::from_seed(seed());
+
+        let mut rng_output = [0u8; 3 * crate::BLOCK_SIZE];
+        rng.fill_bytes(&mut rng_output);
+
+        let mut block = Block::new(&seed(), [0u8; IV_SIZE], ChaChaRng::ROUNDS);
+        let mut block_output = [0u8; 3 * crate::BLOCK_SIZE];
+        for (i, chunk) in block_output.chunks_mut(crate::BLOCK_SIZE).enumerate() {
+            block.generate(i as u64, chunk);
+        }
+
+        assert_eq!(rng_output, block_output);
+    }
+
+    /// `set_word_pos` followed by `get_word_pos` must round-trip, and
+    /// resuming from a saved position must reproduce the same keystream
+    /// word that position originally pointed to.
+    #[test]
+    fn word_pos_round_trip() {
+        let mut rng = ChaChaRng::from_seed(seed());
+
+        for _ in 0..(WORDS_PER_BLOCK + 3) {
+            rng.next_u32();
+        }
+        let pos = rng.get_word_pos();
+        assert_eq!(pos, WORDS_PER_BLOCK + 3);
+
+        let expected = rng.next_u32();
+
+        rng.set_word_pos(pos);
+        assert_eq!(rng.get_word_pos(), pos);
+        assert_eq!(rng.next_u32(), expected);
+    }
+}